@@ -1,9 +1,16 @@
 use colored::Colorize;
+use ignore::WalkBuilder;
+use regex::{Regex, RegexBuilder};
+use serde::Serialize;
+use std::collections::VecDeque;
 use std::env;
+use std::fmt::Write as _;
 use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use std::io::{self, BufRead, BufReader, Read, Write as _};
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
 
 #[derive(Debug, Default, Clone)]
 struct Config {
@@ -13,6 +20,19 @@ struct Config {
     recursive: bool,        // -r
     show_filenames: bool,   // -f
     colored: bool,          // -c
+    regex: bool,            // -E/--regexp
+    smart_case: bool,       // -S
+    after_context: usize,   // -A N
+    before_context: usize,  // -B N
+    count: bool,            // --count
+    files_with_matches: bool, // -l/--files-with-matches
+    files_without_matches: bool, // -L/--files-without-matches
+    hidden: bool,           // --hidden
+    no_ignore: bool,        // --no-ignore
+    globs: Vec<String>,     // --glob
+    json: bool,             // --json
+    threads: usize,         // --threads N (0 = number of logical CPUs)
+    sort: bool,             // --sort
     help: bool,             // -h/--help
     pattern: String,
     targets: Vec<String>,
@@ -27,6 +47,20 @@ Options:
 -r                Recursive directory search
 -f                Print filenames
 -c                Enable colored output
+-E, --regexp      Treat <pattern> as a regular expression
+-S                Smart-case: case-insensitive unless <pattern> has an uppercase letter
+-A N              Print N lines of trailing context after each match
+-B N              Print N lines of leading context before each match
+-C N              Print N lines of context before and after each match
+--count           Print only a count of matching lines per file
+-l, --files-with-matches     Print only the names of files with at least one match
+-L, --files-without-matches  Print only the names of files with no matches
+--hidden          Include hidden files and directories in recursive search
+--no-ignore       Don't respect .gitignore/.ignore files in recursive search
+--glob PATTERN    Only search paths matching PATTERN (prefix with ! to exclude); repeatable
+--json            Emit one JSON object per matching line instead of text
+--threads N       Number of worker threads to search with (default: logical CPUs)
+--sort            Preserve input file order in the output when searching in parallel
 -h, --help        Show help information
 "#;
 
@@ -45,16 +79,48 @@ fn main() {
         return;
     }
 
+    let compiled_globs = match compile_globs(&cfg.globs) {
+        Ok(g) => g,
+        Err(e) => {
+            eprintln!("Invalid --glob pattern: {}", e);
+            return;
+        }
+    };
+
+    let case_insensitive = if cfg.smart_case {
+        !pattern_has_uppercase_char(&cfg.pattern, cfg.regex)
+    } else {
+        cfg.case_insensitive
+    };
+
+    let regex = match build_matcher_regex(&cfg, case_insensitive) {
+        Ok(re) => re,
+        Err(e) => {
+            eprintln!("Invalid pattern: {}", e);
+            return;
+        }
+    };
+
     // Gather files
     let mut files: Vec<PathBuf> = Vec::new();
     for t in &cfg.targets {
         let p = PathBuf::from(t);
         if cfg.recursive {
             if p.is_dir() {
-                for entry in WalkDir::new(&p)
-                    .into_iter()
+                let mut builder = WalkBuilder::new(&p);
+                builder.hidden(!cfg.hidden);
+                if cfg.no_ignore {
+                    builder
+                        .ignore(false)
+                        .git_ignore(false)
+                        .git_global(false)
+                        .git_exclude(false);
+                }
+                for entry in builder
+                    .build()
                     .filter_map(Result::ok)
-                    .filter(|e| e.file_type().is_file())
+                    .filter(|e| e.file_type().is_some_and(|ft| ft.is_file()))
+                    .filter(|e| path_matches_globs(e.path(), &compiled_globs))
                 {
                     files.push(entry.path().to_path_buf());
                 }
@@ -66,20 +132,149 @@ fn main() {
         }
     }
 
-    for f in files {
-        if let Err(e) = search_file(&f, &cfg) {
-            eprintln!("Failed to read {}: {}", f.display(), e);
+    let thread_count = if cfg.threads > 0 {
+        cfg.threads
+    } else {
+        thread::available_parallelism().map_or(1, |n| n.get())
+    }
+    .min(files.len().max(1));
+
+    let chunk_size = files.len().div_ceil(thread_count).max(1);
+    let (tx, rx) = mpsc::channel();
+    let mut handles = Vec::new();
+
+    for (chunk_idx, chunk) in files.chunks(chunk_size).enumerate() {
+        let chunk = chunk.to_vec();
+        let cfg = cfg.clone();
+        let regex = regex.clone();
+        let tx = tx.clone();
+        handles.push(thread::spawn(move || {
+            let m = Matcher {
+                regex: &regex,
+                pattern: &cfg.pattern,
+            };
+            let mut out = String::new();
+            for f in &chunk {
+                if let Err(e) = search_file(f, &cfg, &m, &mut out) {
+                    eprintln!("Failed to read {}: {}", f.display(), e);
+                }
+            }
+            let _ = tx.send((chunk_idx, out));
+        }));
+    }
+    drop(tx);
+
+    if cfg.sort {
+        // --sort needs every chunk in hand before it can restore input
+        // order, so there's no way around buffering here.
+        let results: Vec<(usize, String)> = rx.iter().collect();
+        for out in order_by_chunk(results) {
+            print!("{}", out);
+        }
+    } else {
+        // Print each chunk's output as soon as it arrives instead of
+        // waiting on the slowest worker, so time-to-first-line scales with
+        // the fastest chunk rather than the whole run.
+        for (_, out) in &rx {
+            print!("{}", out);
+            let _ = io::stdout().flush();
+        }
+    }
+
+    for handle in handles {
+        if handle.join().is_err() {
+            eprintln!("A search worker thread panicked; its results were dropped");
         }
     }
 }
 
+// Restores the order files were originally gathered in: worker threads may
+// finish their chunks in any order, so --sort sorts the arrived results by
+// the chunk index they were dispatched with before printing.
+fn order_by_chunk(mut results: Vec<(usize, String)>) -> Vec<String> {
+    results.sort_by_key(|(chunk_idx, _)| *chunk_idx);
+    results.into_iter().map(|(_, out)| out).collect()
+}
+
 fn parse_args(args: Vec<String>) -> Result<Config, ()> {
     let mut cfg = Config::default();
     let mut operands: Vec<String> = Vec::new();
 
-    for a in args {
+    let mut i = 0;
+    while i < args.len() {
+        let a = &args[i];
+
         if a == "-h" || a == "--help" {
             cfg.help = true;
+            i += 1;
+            continue;
+        }
+        if a == "--regexp" {
+            cfg.regex = true;
+            i += 1;
+            continue;
+        }
+        if a == "--count" {
+            cfg.count = true;
+            i += 1;
+            continue;
+        }
+        if a == "--files-with-matches" {
+            cfg.files_with_matches = true;
+            i += 1;
+            continue;
+        }
+        if a == "--files-without-matches" {
+            cfg.files_without_matches = true;
+            i += 1;
+            continue;
+        }
+        if a == "--hidden" {
+            cfg.hidden = true;
+            i += 1;
+            continue;
+        }
+        if a == "--no-ignore" {
+            cfg.no_ignore = true;
+            i += 1;
+            continue;
+        }
+        if a == "--glob" {
+            i += 1;
+            let pat = args.get(i).ok_or(())?;
+            cfg.globs.push(pat.clone());
+            i += 1;
+            continue;
+        }
+        if a == "--json" {
+            cfg.json = true;
+            i += 1;
+            continue;
+        }
+        if a == "--threads" {
+            i += 1;
+            cfg.threads = args.get(i).and_then(|v| v.parse().ok()).ok_or(())?;
+            i += 1;
+            continue;
+        }
+        if a == "--sort" {
+            cfg.sort = true;
+            i += 1;
+            continue;
+        }
+        if a == "-A" || a == "-B" || a == "-C" {
+            i += 1;
+            let n: usize = args.get(i).and_then(|v| v.parse().ok()).ok_or(())?;
+            match a.as_str() {
+                "-A" => cfg.after_context = n,
+                "-B" => cfg.before_context = n,
+                "-C" => {
+                    cfg.after_context = n;
+                    cfg.before_context = n;
+                }
+                _ => unreachable!(),
+            }
+            i += 1;
             continue;
         }
         if a.starts_with('-') && a.len() >= 2 {
@@ -91,12 +286,19 @@ fn parse_args(args: Vec<String>) -> Result<Config, ()> {
                     'r' => cfg.recursive = true,
                     'f' => cfg.show_filenames = true,
                     'c' => cfg.colored = true,
+                    'E' => cfg.regex = true,
+                    'S' => cfg.smart_case = true,
+                    'l' => cfg.files_with_matches = true,
+                    'L' => cfg.files_without_matches = true,
                     'h' => cfg.help = true,
-                    '-' => { /* allow --help handled above */ }
+                    '-' => { /* allow --help/--regexp handled above */ }
+                    _ => return Err(()),
                 }
             }
+            i += 1;
         } else {
-            operands.push(a);
+            operands.push(a.clone());
+            i += 1;
         }
     }
 
@@ -108,76 +310,517 @@ fn parse_args(args: Vec<String>) -> Result<Config, ()> {
     Ok(cfg)
 }
 
-fn search_file(path: &Path, cfg: &Config) -> io::Result<()> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
+// Transparently decompresses known archive extensions by shelling out to the
+// matching external tool; falls back to reading the raw file when no
+// decompressor is found.
+struct DecompressionReader {
+    inner: Box<dyn Read>,
+    child: Option<Child>,
+}
+
+impl DecompressionReader {
+    fn open(path: &Path) -> io::Result<Self> {
+        if let Some((cmd, cmd_args)) = decompression_command(path) {
+            if let Ok(mut child) = Command::new(cmd)
+                .args(cmd_args)
+                .arg(path)
+                .stdout(Stdio::piped())
+                .spawn()
+            {
+                if let Some(stdout) = child.stdout.take() {
+                    return Ok(DecompressionReader {
+                        inner: Box::new(stdout),
+                        child: Some(child),
+                    });
+                }
+            }
+        }
+
+        Ok(DecompressionReader {
+            inner: Box::new(File::open(path)?),
+            child: None,
+        })
+    }
+}
+
+impl Read for DecompressionReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl Drop for DecompressionReader {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            // Close our end of the pipe before waiting: if we stopped reading
+            // early (e.g. -l/-L bail out on the first match), the decompressor
+            // may still be writing, and waiting on it first would deadlock
+            // once the pipe buffer fills.
+            self.inner = Box::new(io::empty());
+            let _ = child.wait();
+        }
+    }
+}
+
+fn decompression_command(path: &Path) -> Option<(&'static str, &'static [&'static str])> {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some("gz") => Some(("gzip", &["-d", "-c"])),
+        Some("bz2") => Some(("bzip2", &["-d", "-c"])),
+        Some("xz") => Some(("xz", &["-d", "-c"])),
+        Some("zst") => Some(("zstd", &["-d", "-c"])),
+        _ => None,
+    }
+}
+
+// Bundles the compiled matcher together with the literal fallback needed to
+// evaluate, highlight, and report spans for a single search, so functions
+// downstream of `search_file` don't each need their own trio of parameters.
+struct Matcher<'a> {
+    regex: &'a Option<Regex>,
+    pattern: &'a str,
+}
+
+impl Matcher<'_> {
+    fn is_match(&self, line: &str) -> bool {
+        match self.regex {
+            Some(re) => re.is_match(line),
+            None => contains(line, self.pattern),
+        }
+    }
+
+    fn highlight(&self, line: &str) -> String {
+        match self.regex {
+            Some(re) => highlight_regex(line, re),
+            None => highlight(line, self.pattern),
+        }
+    }
 
-    let pattern = if cfg.case_insensitive {
-        cfg.pattern.to_lowercase()
+    fn spans(&self, line: &str) -> Vec<(usize, usize)> {
+        match_spans(line, self.regex, self.pattern)
+    }
+}
+
+// Compiles the regex backing a search, if any: -E patterns compile as
+// written, and case-insensitive literal patterns compile escaped, so their
+// match spans stay aligned to the original line's bytes (lowercasing the line
+// can change its byte length, e.g. 'İ' becomes the 3-byte "i̇").
+// Built once per run and cheaply cloned (Regex's clone is an Arc bump) into
+// each worker thread, rather than rebuilt per file.
+fn build_matcher_regex(
+    cfg: &Config,
+    case_insensitive: bool,
+) -> Result<Option<Regex>, regex::Error> {
+    if cfg.regex {
+        Ok(Some(
+            RegexBuilder::new(&cfg.pattern)
+                .case_insensitive(case_insensitive)
+                .build()?,
+        ))
+    } else if case_insensitive {
+        Ok(Some(
+            RegexBuilder::new(&regex::escape(&cfg.pattern))
+                .case_insensitive(true)
+                .build()?,
+        ))
     } else {
-        cfg.pattern.clone()
-    };
+        Ok(None)
+    }
+}
+
+fn search_file(path: &Path, cfg: &Config, m: &Matcher, out: &mut String) -> io::Result<()> {
+    let reader = BufReader::new(DecompressionReader::open(path)?);
+
+    if cfg.files_with_matches || cfg.files_without_matches {
+        return search_file_names_only(path, cfg, reader, m, out);
+    }
+
+    if cfg.count {
+        return search_file_count(path, cfg, reader, m, out);
+    }
+
+    let mut before_buf: VecDeque<(usize, String)> = VecDeque::with_capacity(cfg.before_context);
+    let mut after_remaining = 0usize;
+    let mut last_printed_idx: Option<usize> = None;
 
     for (idx, line_res) in reader.lines().enumerate() {
         let line = line_res?;
-        let is_match = contains(&line, &pattern, cfg.case_insensitive);
-
+        let is_match = m.is_match(&line);
         let pass = if cfg.invert_match { !is_match } else { is_match };
-        if !pass {
+
+        if cfg.json {
+            if pass {
+                print_json_match(path, idx, &line, m, out)?;
+            }
             continue;
         }
 
-        let mut out = String::new();
-
-        if cfg.show_filenames {
-            out.push_str(&format!("{}: ", path.display()));
+        if pass {
+            let first_idx = before_buf.front().map_or(idx, |(b_idx, _)| *b_idx);
+            if let Some(last) = last_printed_idx {
+                if first_idx > last + 1 {
+                    let _ = writeln!(out, "--");
+                }
+            }
+            for (b_idx, b_line) in before_buf.drain(..) {
+                print_line(path, cfg, m, b_idx, &b_line, LineRole::Context, out);
+            }
+            print_line(
+                path,
+                cfg,
+                m,
+                idx,
+                &line,
+                LineRole::Primary {
+                    highlight: is_match && !cfg.invert_match,
+                },
+                out,
+            );
+            last_printed_idx = Some(idx);
+            after_remaining = cfg.after_context;
+        } else if after_remaining > 0 {
+            print_line(path, cfg, m, idx, &line, LineRole::Context, out);
+            last_printed_idx = Some(idx);
+            after_remaining -= 1;
+        } else if cfg.before_context > 0 {
+            before_buf.push_back((idx, line));
+            if before_buf.len() > cfg.before_context {
+                before_buf.pop_front();
+            }
         }
+    }
 
-        if cfg.show_line_numbers {
-            out.push_str(&format!("{}: ", idx + 1));
-        }
+    Ok(())
+}
 
-        if cfg.colored && is_match && !cfg.invert_match {
-            out.push_str(&highlight(&line, &pattern, cfg.case_insensitive));
-        } else {
-            out.push_str(&line);
+#[derive(Serialize)]
+struct Submatch {
+    start: usize,
+    end: usize,
+}
+
+#[derive(Serialize)]
+struct JsonMatch<'a> {
+    path: String,
+    line_number: usize,
+    line: &'a str,
+    submatches: Vec<Submatch>,
+}
+
+fn print_json_match(
+    path: &Path,
+    idx: usize,
+    line: &str,
+    m: &Matcher,
+    out: &mut String,
+) -> io::Result<()> {
+    let submatches = m
+        .spans(line)
+        .into_iter()
+        .map(|(start, end)| Submatch { start, end })
+        .collect();
+
+    let record = JsonMatch {
+        path: path.display().to_string(),
+        line_number: idx + 1,
+        line,
+        submatches,
+    };
+
+    let json = serde_json::to_string(&record).map_err(io::Error::other)?;
+    let _ = writeln!(out, "{}", json);
+    Ok(())
+}
+
+fn match_spans(line: &str, matcher: &Option<Regex>, pattern: &str) -> Vec<(usize, usize)> {
+    if let Some(re) = matcher {
+        return re.find_iter(line).map(|m| (m.start(), m.end())).collect();
+    }
+
+    if pattern.is_empty() {
+        return Vec::new();
+    }
+
+    let mut spans = Vec::new();
+    let mut i = 0;
+    while let Some(pos) = line[i..].find(pattern) {
+        let start = i + pos;
+        let end = start + pattern.len();
+        spans.push((start, end));
+        i = end;
+    }
+    spans
+}
+
+// Distinguishes the line a search hit (or, under -v, its inverted
+// counterpart) from the surrounding -A/-B/-C context: the former gets the
+// ':' separator and, with -c colored output, the highlighted match text; the
+// latter always gets '-' and is never highlighted, even if it happens to
+// contain a literal match (e.g. non-inverted context around a -v hit).
+enum LineRole {
+    Context,
+    Primary { highlight: bool },
+}
+
+fn print_line(
+    path: &Path,
+    cfg: &Config,
+    m: &Matcher,
+    idx: usize,
+    line: &str,
+    role: LineRole,
+    out: &mut String,
+) {
+    let highlight = matches!(role, LineRole::Primary { highlight: true });
+    let sep = if matches!(role, LineRole::Primary { .. }) {
+        ':'
+    } else {
+        '-'
+    };
+
+    if cfg.show_filenames {
+        let _ = write!(out, "{}{} ", path.display(), sep);
+    }
+
+    if cfg.show_line_numbers {
+        let _ = write!(out, "{}{} ", idx + 1, sep);
+    }
+
+    if cfg.colored && highlight {
+        out.push_str(&m.highlight(line));
+    } else {
+        out.push_str(line);
+    }
+
+    out.push('\n');
+}
+
+fn search_file_names_only(
+    path: &Path,
+    cfg: &Config,
+    reader: BufReader<DecompressionReader>,
+    m: &Matcher,
+    out: &mut String,
+) -> io::Result<()> {
+    let mut matched = false;
+
+    for line_res in reader.lines() {
+        let line = line_res?;
+        let is_match = m.is_match(&line);
+        let pass = if cfg.invert_match { !is_match } else { is_match };
+        if pass {
+            matched = true;
+            break;
         }
+    }
 
-        println!("{}", out);
+    if (matched && cfg.files_with_matches) || (!matched && cfg.files_without_matches) {
+        let _ = writeln!(out, "{}", path.display());
     }
 
     Ok(())
 }
 
-fn contains(line: &str, pattern: &str, case_insensitive: bool) -> bool {
-    if case_insensitive {
-        line.to_lowercase().contains(pattern)
-    } else {
-        line.contains(pattern)
+fn search_file_count(
+    path: &Path,
+    cfg: &Config,
+    reader: BufReader<DecompressionReader>,
+    m: &Matcher,
+    out: &mut String,
+) -> io::Result<()> {
+    let mut count = 0usize;
+
+    for line_res in reader.lines() {
+        let line = line_res?;
+        let is_match = m.is_match(&line);
+        let pass = if cfg.invert_match { !is_match } else { is_match };
+        if pass {
+            count += 1;
+        }
+    }
+
+    let _ = writeln!(out, "{}: {}", path.display(), count);
+    Ok(())
+}
+
+struct CompiledGlob {
+    regex: Regex,
+    exclude: bool,
+}
+
+fn glob_to_regex(glob: &str) -> String {
+    let escaped = regex::escape(glob);
+    let translated = escaped.replace("\\*", ".*").replace("\\?", ".");
+    format!("^{}$", translated)
+}
+
+fn compile_globs(globs: &[String]) -> Result<Vec<CompiledGlob>, regex::Error> {
+    globs
+        .iter()
+        .map(|g| {
+            let (exclude, pat) = match g.strip_prefix('!') {
+                Some(rest) => (true, rest),
+                None => (false, g.as_str()),
+            };
+            Regex::new(&glob_to_regex(pat)).map(|regex| CompiledGlob { regex, exclude })
+        })
+        .collect()
+}
+
+fn path_matches_globs(path: &Path, globs: &[CompiledGlob]) -> bool {
+    if globs.is_empty() {
+        return true;
     }
+
+    let path_str = path.to_string_lossy();
+    if globs.iter().any(|g| g.exclude && g.regex.is_match(&path_str)) {
+        return false;
+    }
+
+    let includes: Vec<&CompiledGlob> = globs.iter().filter(|g| !g.exclude).collect();
+    includes.is_empty() || includes.iter().any(|g| g.regex.is_match(&path_str))
 }
 
-fn highlight(line: &str, pattern: &str, case_insensitive: bool) -> String {
+fn pattern_has_uppercase_char(pattern: &str, regex_mode: bool) -> bool {
+    let mut escaped = false;
+    for ch in pattern.chars() {
+        if regex_mode && !escaped && ch == '\\' {
+            escaped = true;
+            continue;
+        }
+        if !escaped && ch.is_uppercase() {
+            return true;
+        }
+        escaped = false;
+    }
+    false
+}
+
+fn contains(line: &str, pattern: &str) -> bool {
+    line.contains(pattern)
+}
+
+fn highlight(line: &str, pattern: &str) -> String {
     if pattern.is_empty() {
         return line.to_string();
     }
 
-    let (haystack, needle) = if case_insensitive {
-        (line.to_lowercase(), pattern.to_lowercase())
-    } else {
-        (line.to_string(), pattern.to_string())
-    };
-
     let mut result = String::with_capacity(line.len());
     let mut i = 0;
-    while let Some(pos) = haystack[i..].find(&needle) {
+    while let Some(pos) = line[i..].find(pattern) {
         let start = i + pos;
-        let end = start + needle.len();
+        let end = start + pattern.len();
         result.push_str(&line[i..start]);
         result.push_str(&line[start..end].red().to_string());
         i = end;
     }
-    
+
+    result.push_str(&line[i..]);
+    result
+}
+
+fn highlight_regex(line: &str, re: &Regex) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut i = 0;
+    for m in re.find_iter(line) {
+        result.push_str(&line[i..m.start()]);
+        result.push_str(&line[m.start()..m.end()].red().to_string());
+        i = m.end();
+    }
+
     result.push_str(&line[i..]);
     result
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn match_spans_literal_reports_all_occurrences() {
+        let spans = match_spans("foo bar foo", &None, "foo");
+        assert_eq!(spans, vec![(0, 3), (8, 11)]);
+    }
+
+    #[test]
+    fn match_spans_regex_reports_byte_offsets() {
+        let re = Some(Regex::new("ba.").unwrap());
+        let spans = match_spans("foo bar baz", &re, "");
+        assert_eq!(spans, vec![(4, 7), (8, 11)]);
+    }
+
+    #[test]
+    fn match_spans_case_insensitive_stays_aligned_to_original_bytes() {
+        // 'İ' (U+0130) case-folds to a 3-byte "i̇", so a naive `.to_lowercase()`
+        // comparison would report offsets that no longer line up with `line`.
+        let re = Some(
+            RegexBuilder::new(&regex::escape("foo"))
+                .case_insensitive(true)
+                .build()
+                .unwrap(),
+        );
+        let line = "XİY foo";
+        let spans = match_spans(line, &re, "foo");
+        assert_eq!(spans, vec![(5, 8)]);
+        assert_eq!(&line[5..8], "foo");
+    }
+
+    #[test]
+    fn pattern_has_uppercase_char_detects_uppercase_letters() {
+        assert!(!pattern_has_uppercase_char("hello", false));
+        assert!(pattern_has_uppercase_char("Hello", false));
+    }
+
+    #[test]
+    fn pattern_has_uppercase_char_ignores_escaped_chars_in_regex_mode() {
+        // `\S` is an uppercase-looking escape, not a literal uppercase letter.
+        assert!(!pattern_has_uppercase_char(r"\S+", true));
+        assert!(pattern_has_uppercase_char(r"\SFoo", true));
+    }
+
+    #[test]
+    fn decompression_reader_round_trips_a_gzip_file() {
+        let dir = env::temp_dir().join(format!("rust-grep-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("round_trip.gz");
+
+        let contents = "line one\nline two\nline three\n";
+        let gzip = Command::new("gzip")
+            .arg("-c")
+            .stdin(Stdio::piped())
+            .stdout(std::fs::File::create(&path).unwrap())
+            .spawn();
+        let mut gzip = match gzip {
+            Ok(child) => child,
+            Err(_) => {
+                // gzip isn't installed on this machine; nothing to verify.
+                let _ = std::fs::remove_dir_all(&dir);
+                return;
+            }
+        };
+        gzip.stdin
+            .take()
+            .unwrap()
+            .write_all(contents.as_bytes())
+            .unwrap();
+        gzip.wait().unwrap();
+
+        let mut reader = DecompressionReader::open(&path).unwrap();
+        let mut buf = String::new();
+        reader.read_to_string(&mut buf).unwrap();
+
+        assert_eq!(buf, contents);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn order_by_chunk_restores_input_order_regardless_of_arrival_order() {
+        // Simulates chunk 2 finishing before chunks 0 and 1, as can happen
+        // when search time varies across a worker pool.
+        let arrived = vec![
+            (2, "c\n".to_string()),
+            (0, "a\n".to_string()),
+            (1, "b\n".to_string()),
+        ];
+        assert_eq!(order_by_chunk(arrived), vec!["a\n", "b\n", "c\n"]);
+    }
+}